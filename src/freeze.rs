@@ -0,0 +1,149 @@
+//! Embed a formatted Python module into the launcher binary and import it from memory at startup.
+//!
+//! This takes the module-embedding half of PyOxidizer's `pyembed` subsystem (its
+//! `importer.rs`/`data.rs`, which import modules from an in-memory blob): `freeze` appends a blob
+//! holding the formatted source to a copy of the running launcher, and on startup [`read_embedded`]
+//! detects the trailer and hands the source back. The interpreter then imports the module through
+//! an in-memory meta-path finder ([`bootstrap_source`]) installed via the `PyConfig` init, so the
+//! frozen app runs its code straight from memory instead of a script file on disk.
+//!
+//! Scope: only the formatted *source* is embedded, not the interpreter. The libpython is still
+//! resolved at runtime by the usual `run()` path (provisioned into the cache dir, or supplied via
+//! the `PYPP_PYTHON_*` escape hatch). Bundling a relocatable libpython into the binary the way
+//! PyOxidizer does is out of scope here; a frozen binary removes the script file, not the
+//! interpreter dependency.
+
+use fs_err as fs;
+use ruff_python_formatter::{format_module_source, FormatModuleError, PyFormatOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::debug;
+
+#[derive(Debug, Error)]
+pub enum FreezeError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Invalid python code")]
+    FormatModule(#[from] FormatModuleError),
+    #[error("Couldn't locate the running executable")]
+    CurrentExe(#[source] io::Error),
+    #[error("Usage: {} freeze <script.py> [-o <output>]", env!("CARGO_PKG_NAME"))]
+    MissingScript,
+}
+
+/// Trailer magic, bumped if the blob layout ever changes.
+const MAGIC: &[u8; 8] = b"PYPPFRZ1";
+
+/// The name the frozen module is imported under before it's run as `__main__`.
+const MODULE_NAME: &str = "__pypp_frozen__";
+
+/// `freeze`/`build` subcommand: format `script` the same way `run()` does, then emit a standalone
+/// executable that is this launcher with the formatted source appended.
+pub fn build_command(args: &[String]) -> Result<(), FreezeError> {
+    let mut script: Option<&String> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = rest.next().map(PathBuf::from),
+            _ => script = Some(arg),
+        }
+    }
+    let script = script.ok_or(FreezeError::MissingScript)?;
+    let script = Path::new(script);
+    let output = output.unwrap_or_else(|| {
+        // `app.py` -> `app`, matching the usual expectation for a built binary.
+        PathBuf::from(script.file_stem().unwrap_or(script.as_os_str()))
+    });
+
+    let content = fs::read_to_string(script)?;
+    let formatted = format_module_source(&content, PyFormatOptions::default())?;
+
+    let current_exe = std::env::current_exe().map_err(FreezeError::CurrentExe)?;
+    let mut binary = fs::read(&current_exe)?;
+    // If we're building from an already-frozen launcher, drop the old blob first so we don't stack
+    // trailers.
+    if let Some(existing) = embedded_in(&binary) {
+        binary.truncate(binary.len() - blob_len(existing.len()));
+    }
+    let source = formatted.as_code().as_bytes();
+    binary.extend_from_slice(source);
+    binary.extend_from_slice(&(source.len() as u64).to_le_bytes());
+    binary.extend_from_slice(MAGIC);
+    fs::write(&output, &binary)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&output)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&output, perms)?;
+    }
+
+    debug!("Froze {} into {}", script.display(), output.display());
+    Ok(())
+}
+
+/// Return the embedded formatted source if the running executable carries a frozen blob.
+pub fn read_embedded() -> Result<Option<String>, FreezeError> {
+    let current_exe = std::env::current_exe().map_err(FreezeError::CurrentExe)?;
+    let binary = fs::read(&current_exe)?;
+    Ok(embedded_in(&binary).map(|source| String::from_utf8_lossy(source).into_owned()))
+}
+
+/// Total trailer size for a payload of `source_len` bytes: the source, its length, and the magic.
+fn blob_len(source_len: usize) -> usize {
+    source_len + 8 + MAGIC.len()
+}
+
+/// Parse the trailer at the end of `binary`, returning the embedded source bytes if present.
+fn embedded_in(binary: &[u8]) -> Option<&[u8]> {
+    let trailer = 8 + MAGIC.len();
+    if binary.len() < trailer {
+        return None;
+    }
+    let (head, magic) = binary.split_at(binary.len() - MAGIC.len());
+    if magic != MAGIC {
+        return None;
+    }
+    let len_bytes: [u8; 8] = head[head.len() - 8..].try_into().ok()?;
+    let source_len = u64::from_le_bytes(len_bytes) as usize;
+    let source_end = head.len() - 8;
+    source_end.checked_sub(source_len).map(|start| &head[start..source_end])
+}
+
+/// Build the `-c` bootstrap that installs an in-memory meta-path finder for the frozen module and
+/// runs it as `__main__`, so the source is imported from memory instead of a file on disk.
+pub fn bootstrap_source(module_source: &str) -> String {
+    format!(
+        "import sys, importlib.abc, importlib.util, runpy\n\
+         _SRC = {source}.decode('utf-8')\n\
+         _NAME = '{name}'\n\
+         class _FrozenFinder(importlib.abc.MetaPathFinder, importlib.abc.InspectLoader):\n\
+         \x20   def find_spec(self, name, path, target=None):\n\
+         \x20       if name == _NAME:\n\
+         \x20           return importlib.util.spec_from_loader(name, self)\n\
+         \x20       return None\n\
+         \x20   def get_source(self, name):\n\
+         \x20       return _SRC\n\
+         \x20   def get_code(self, name):\n\
+         \x20       return compile(_SRC, '<frozen %s>' % _NAME, 'exec')\n\
+         sys.meta_path.insert(0, _FrozenFinder())\n\
+         runpy.run_module(_NAME, run_name='__main__', alter_sys=True)\n",
+        source = py_bytes_literal(module_source),
+        name = MODULE_NAME,
+    )
+}
+
+/// Emit `module_source` as an escaped Python bytes literal, which sidesteps any quoting headaches
+/// when we splice it into the `-c` bootstrap.
+fn py_bytes_literal(module_source: &str) -> String {
+    let mut out = String::with_capacity(module_source.len() * 4 + 3);
+    out.push_str("b'");
+    for byte in module_source.as_bytes() {
+        out.push_str(&format!("\\x{byte:02x}"));
+    }
+    out.push('\'');
+    out
+}
@@ -1,13 +1,18 @@
+mod freeze;
+
 use fs_err as fs;
+use freeze::FreezeError;
 use libc::wchar_t;
 use libloading::Library;
 use monotrail_utils::parse_cpython_args::{determine_python_version, naive_python_arg_parser};
 use monotrail_utils::standalone_python::provision_python;
 use ruff_python_formatter::{format_module_source, FormatModuleError, PyFormatOptions};
 use std::error::Error;
-use std::ffi::{c_int, c_void};
+use std::ffi::{c_char, c_int, c_ulong, c_void, CString};
+use std::ptr::addr_of_mut;
 use std::mem::MaybeUninit;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::{env, io};
 use tempfile::NamedTempFile;
 use thiserror::Error;
@@ -36,6 +41,64 @@ enum PythonPlusPlusError {
     MissingScript,
     #[error("Invalid python code")]
     FormatModule(#[from] FormatModuleError),
+    #[error("Unsupported python interpreter `{0}`, only CPython and PyPy are supported")]
+    UnsupportedInterpreter(String),
+    #[error("Failed to initialize PyPy: {0}")]
+    PyPyInit(String),
+    #[error(transparent)]
+    Freeze(#[from] FreezeError),
+    #[error("Invalid PYPP_PYTHON_VERSION `{0}`, expected something like `3.11`")]
+    InvalidExternalVersion(String),
+}
+
+/// An interpreter supplied directly through the environment, the equivalent of pyo3's
+/// `PYO3_NO_PYTHON` escape hatch: when `PYPP_PYTHON_HOME`/`PYPP_PYTHON_VERSION` (and optionally
+/// `PYPP_LIBPYTHON`, read in [`resolve_libpython`]) are set we skip [`provision_python`] entirely
+/// and launch the given interpreter, which is essential for constrained, offline, or cross
+/// environments the monotrail provisioner doesn't know how to fetch.
+struct ExternalInterpreter {
+    python_home: PathBuf,
+    python_version: (u8, u8),
+    python_binary: PathBuf,
+}
+
+/// Populate an [`ExternalInterpreter`] from the environment, or `None` when the escape hatch isn't
+/// in use (`PYPP_PYTHON_HOME` unset).
+fn external_interpreter_from_env() -> Result<Option<ExternalInterpreter>, PythonPlusPlusError> {
+    let Some(python_home) = env::var_os("PYPP_PYTHON_HOME") else {
+        return Ok(None);
+    };
+    let python_home = PathBuf::from(python_home);
+
+    let version = env::var("PYPP_PYTHON_VERSION")
+        .map_err(|_| PythonPlusPlusError::InvalidExternalVersion(String::new()))?;
+    let python_version = version
+        .split_once('.')
+        .and_then(|(major, minor)| Some((major.parse().ok()?, minor.parse().ok()?)))
+        .ok_or_else(|| PythonPlusPlusError::InvalidExternalVersion(version.clone()))?;
+
+    // sys.executable for the supplied interpreter; the actual shared library is resolved separately
+    // (honouring PYPP_LIBPYTHON) in resolve_libpython.
+    let python_binary = if cfg!(target_os = "windows") {
+        python_home.join("python.exe")
+    } else {
+        python_home.join("bin").join("python3")
+    };
+
+    Ok(Some(ExternalInterpreter {
+        python_home,
+        python_version,
+        python_binary,
+    }))
+}
+
+/// Which interpreter we're launching. The shared-library name, the symbols that are available, and
+/// the init sequence all differ between implementations, the same distinction the external build
+/// scripts draw with their `PythonInterpreterKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InterpreterKind {
+    CPython,
+    PyPy,
 }
 
 /// <https://docs.python.org/3/c-api/init_config.html#preinitialize-python-with-pypreconfig>
@@ -80,6 +143,326 @@ pub struct PyStatus {
     pub exitcode: c_int,
 }
 
+/// <https://docs.python.org/3/c-api/init_config.html#c.PyWideStringList>
+///
+/// <https://docs.rs/pyo3/0.16.5/pyo3/ffi/struct.PyWideStringList.html>
+#[repr(C)]
+#[derive(Debug)]
+pub struct PyWideStringList {
+    pub length: isize,
+    pub items: *mut *mut wchar_t,
+}
+
+/// The `PyConfig` fields are offsets in a `#[repr(C)]` struct, so the layout has to match the
+/// CPython ABI exactly. CPython grows the struct between minor versions (and guards some fields
+/// behind `MS_WINDOWS`/`__APPLE__`), so we mirror one struct per supported minor version and pick
+/// the matching one at runtime from the detected `python_version`, the same way we already mirror
+/// `PyPreConfig`.
+///
+/// We only ever read the offsets of `home` and `program_name` (everything else is written by
+/// `PyConfig_InitPythonConfig`/`PyConfig_SetArgv`), so the trait just hands those two fields back.
+///
+/// <https://docs.python.org/3/c-api/init_config.html#c.PyConfig>
+trait PyConfigAbi: Sized {
+    unsafe fn home(config: *mut Self) -> *mut *mut wchar_t;
+    unsafe fn program_name(config: *mut Self) -> *mut *mut wchar_t;
+}
+
+/// Generate a `#[repr(C)]` `PyConfig` mirror plus its [`PyConfigAbi`] impl. Fields carry their own
+/// `#[cfg]` attributes so the platform-gated members line up with the CPython build, exactly like
+/// `legacy_windows_fs_encoding` in [`PyPreConfig`].
+macro_rules! py_config {
+    ($name:ident { $($(#[$attr:meta])* $field:ident : $ty:ty),+ $(,)? }) => {
+        #[repr(C)]
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        pub struct $name {
+            $($(#[$attr])* pub $field: $ty,)+
+        }
+
+        impl PyConfigAbi for $name {
+            unsafe fn home(config: *mut Self) -> *mut *mut wchar_t {
+                addr_of_mut!((*config).home)
+            }
+            unsafe fn program_name(config: *mut Self) -> *mut *mut wchar_t {
+                addr_of_mut!((*config).program_name)
+            }
+        }
+    };
+}
+
+py_config!(PyConfig310 {
+    _config_init: c_int,
+    isolated: c_int,
+    use_environment: c_int,
+    dev_mode: c_int,
+    install_signal_handlers: c_int,
+    use_hash_seed: c_int,
+    hash_seed: c_ulong,
+    faulthandler: c_int,
+    tracemalloc: c_int,
+    import_time: c_int,
+    show_ref_count: c_int,
+    dump_refs: c_int,
+    malloc_stats: c_int,
+    filesystem_encoding: *mut wchar_t,
+    filesystem_errors: *mut wchar_t,
+    pycache_prefix: *mut wchar_t,
+    parse_argv: c_int,
+    orig_argv: PyWideStringList,
+    argv: PyWideStringList,
+    xoptions: PyWideStringList,
+    warnoptions: PyWideStringList,
+    site_import: c_int,
+    bytes_warning: c_int,
+    warn_default_encoding: c_int,
+    inspect: c_int,
+    interactive: c_int,
+    optimization_level: c_int,
+    parser_debug: c_int,
+    write_bytecode: c_int,
+    verbose: c_int,
+    quiet: c_int,
+    user_site_directory: c_int,
+    configure_c_stdio: c_int,
+    buffered_stdio: c_int,
+    stdio_encoding: *mut wchar_t,
+    stdio_errors: *mut wchar_t,
+    #[cfg(windows)]
+    legacy_windows_stdio: c_int,
+    check_hash_pycs_mode: *mut wchar_t,
+    pathconfig_warnings: c_int,
+    program_name: *mut wchar_t,
+    pythonpath_env: *mut wchar_t,
+    home: *mut wchar_t,
+    platlibdir: *mut wchar_t,
+    module_search_paths_set: c_int,
+    module_search_paths: PyWideStringList,
+    executable: *mut wchar_t,
+    base_executable: *mut wchar_t,
+    prefix: *mut wchar_t,
+    base_prefix: *mut wchar_t,
+    exec_prefix: *mut wchar_t,
+    base_exec_prefix: *mut wchar_t,
+    skip_source_first_line: c_int,
+    run_command: *mut wchar_t,
+    run_module: *mut wchar_t,
+    run_filename: *mut wchar_t,
+    _install_importlib: c_int,
+    _init_main: c_int,
+    _isolated_interpreter: c_int,
+});
+
+py_config!(PyConfig311 {
+    _config_init: c_int,
+    isolated: c_int,
+    use_environment: c_int,
+    dev_mode: c_int,
+    install_signal_handlers: c_int,
+    use_hash_seed: c_int,
+    hash_seed: c_ulong,
+    faulthandler: c_int,
+    tracemalloc: c_int,
+    import_time: c_int,
+    code_debug_ranges: c_int,
+    show_ref_count: c_int,
+    dump_refs: c_int,
+    dump_refs_file: *mut wchar_t,
+    malloc_stats: c_int,
+    filesystem_encoding: *mut wchar_t,
+    filesystem_errors: *mut wchar_t,
+    pycache_prefix: *mut wchar_t,
+    parse_argv: c_int,
+    orig_argv: PyWideStringList,
+    argv: PyWideStringList,
+    xoptions: PyWideStringList,
+    warnoptions: PyWideStringList,
+    site_import: c_int,
+    bytes_warning: c_int,
+    warn_default_encoding: c_int,
+    inspect: c_int,
+    interactive: c_int,
+    optimization_level: c_int,
+    parser_debug: c_int,
+    write_bytecode: c_int,
+    verbose: c_int,
+    quiet: c_int,
+    user_site_directory: c_int,
+    configure_c_stdio: c_int,
+    buffered_stdio: c_int,
+    stdio_encoding: *mut wchar_t,
+    stdio_errors: *mut wchar_t,
+    #[cfg(windows)]
+    legacy_windows_stdio: c_int,
+    check_hash_pycs_mode: *mut wchar_t,
+    use_frozen_modules: c_int,
+    safe_path: c_int,
+    int_max_str_digits: c_int,
+    pathconfig_warnings: c_int,
+    program_name: *mut wchar_t,
+    pythonpath_env: *mut wchar_t,
+    home: *mut wchar_t,
+    platlibdir: *mut wchar_t,
+    module_search_paths_set: c_int,
+    module_search_paths: PyWideStringList,
+    stdlib_dir: *mut wchar_t,
+    executable: *mut wchar_t,
+    base_executable: *mut wchar_t,
+    prefix: *mut wchar_t,
+    base_prefix: *mut wchar_t,
+    exec_prefix: *mut wchar_t,
+    base_exec_prefix: *mut wchar_t,
+    skip_source_first_line: c_int,
+    run_command: *mut wchar_t,
+    run_module: *mut wchar_t,
+    run_filename: *mut wchar_t,
+    _install_importlib: c_int,
+    _init_main: c_int,
+    _is_python_build: c_int,
+});
+
+py_config!(PyConfig312 {
+    _config_init: c_int,
+    isolated: c_int,
+    use_environment: c_int,
+    dev_mode: c_int,
+    install_signal_handlers: c_int,
+    use_hash_seed: c_int,
+    hash_seed: c_ulong,
+    faulthandler: c_int,
+    tracemalloc: c_int,
+    perf_profiling: c_int,
+    import_time: c_int,
+    code_debug_ranges: c_int,
+    show_ref_count: c_int,
+    dump_refs: c_int,
+    dump_refs_file: *mut wchar_t,
+    malloc_stats: c_int,
+    filesystem_encoding: *mut wchar_t,
+    filesystem_errors: *mut wchar_t,
+    pycache_prefix: *mut wchar_t,
+    parse_argv: c_int,
+    orig_argv: PyWideStringList,
+    argv: PyWideStringList,
+    xoptions: PyWideStringList,
+    warnoptions: PyWideStringList,
+    site_import: c_int,
+    bytes_warning: c_int,
+    warn_default_encoding: c_int,
+    inspect: c_int,
+    interactive: c_int,
+    optimization_level: c_int,
+    parser_debug: c_int,
+    write_bytecode: c_int,
+    verbose: c_int,
+    quiet: c_int,
+    user_site_directory: c_int,
+    configure_c_stdio: c_int,
+    buffered_stdio: c_int,
+    stdio_encoding: *mut wchar_t,
+    stdio_errors: *mut wchar_t,
+    #[cfg(windows)]
+    legacy_windows_stdio: c_int,
+    check_hash_pycs_mode: *mut wchar_t,
+    use_frozen_modules: c_int,
+    safe_path: c_int,
+    int_max_str_digits: c_int,
+    pathconfig_warnings: c_int,
+    program_name: *mut wchar_t,
+    pythonpath_env: *mut wchar_t,
+    home: *mut wchar_t,
+    platlibdir: *mut wchar_t,
+    module_search_paths_set: c_int,
+    module_search_paths: PyWideStringList,
+    stdlib_dir: *mut wchar_t,
+    executable: *mut wchar_t,
+    base_executable: *mut wchar_t,
+    prefix: *mut wchar_t,
+    base_prefix: *mut wchar_t,
+    exec_prefix: *mut wchar_t,
+    base_exec_prefix: *mut wchar_t,
+    skip_source_first_line: c_int,
+    run_command: *mut wchar_t,
+    run_module: *mut wchar_t,
+    run_filename: *mut wchar_t,
+    _install_importlib: c_int,
+    _init_main: c_int,
+    _is_python_build: c_int,
+});
+
+py_config!(PyConfig313 {
+    _config_init: c_int,
+    isolated: c_int,
+    use_environment: c_int,
+    dev_mode: c_int,
+    install_signal_handlers: c_int,
+    use_hash_seed: c_int,
+    hash_seed: c_ulong,
+    faulthandler: c_int,
+    tracemalloc: c_int,
+    perf_profiling: c_int,
+    import_time: c_int,
+    code_debug_ranges: c_int,
+    show_ref_count: c_int,
+    dump_refs: c_int,
+    dump_refs_file: *mut wchar_t,
+    malloc_stats: c_int,
+    filesystem_encoding: *mut wchar_t,
+    filesystem_errors: *mut wchar_t,
+    pycache_prefix: *mut wchar_t,
+    parse_argv: c_int,
+    orig_argv: PyWideStringList,
+    argv: PyWideStringList,
+    xoptions: PyWideStringList,
+    warnoptions: PyWideStringList,
+    site_import: c_int,
+    bytes_warning: c_int,
+    warn_default_encoding: c_int,
+    inspect: c_int,
+    interactive: c_int,
+    optimization_level: c_int,
+    parser_debug: c_int,
+    write_bytecode: c_int,
+    verbose: c_int,
+    quiet: c_int,
+    user_site_directory: c_int,
+    configure_c_stdio: c_int,
+    buffered_stdio: c_int,
+    stdio_encoding: *mut wchar_t,
+    stdio_errors: *mut wchar_t,
+    #[cfg(windows)]
+    legacy_windows_stdio: c_int,
+    check_hash_pycs_mode: *mut wchar_t,
+    use_frozen_modules: c_int,
+    safe_path: c_int,
+    int_max_str_digits: c_int,
+    cpu_count: c_int,
+    #[cfg(target_os = "macos")]
+    use_system_logger: c_int,
+    pathconfig_warnings: c_int,
+    program_name: *mut wchar_t,
+    pythonpath_env: *mut wchar_t,
+    home: *mut wchar_t,
+    platlibdir: *mut wchar_t,
+    sys_path_0: *mut wchar_t,
+    module_search_paths_set: c_int,
+    module_search_paths: PyWideStringList,
+    stdlib_dir: *mut wchar_t,
+    executable: *mut wchar_t,
+    base_executable: *mut wchar_t,
+    prefix: *mut wchar_t,
+    base_prefix: *mut wchar_t,
+    exec_prefix: *mut wchar_t,
+    base_exec_prefix: *mut wchar_t,
+    skip_source_first_line: c_int,
+    run_command: *mut wchar_t,
+    run_module: *mut wchar_t,
+    run_filename: *mut wchar_t,
+    _install_importlib: c_int,
+    _init_main: c_int,
+    _is_python_build: c_int,
+});
+
 /// Set utf-8 mode through pre-init
 ///
 /// <https://docs.python.org/3/c-api/init_config.html#preinitialize-python-with-pypreconfig>
@@ -119,6 +502,384 @@ unsafe fn pre_init(lib: &Library) -> Result<(), PythonPlusPlusError> {
     Ok(())
 }
 
+/// Initialize and run the interpreter through the `PyConfig` flow that replaces the deprecated
+/// `Py_SetPythonHome`/`Py_SetProgramName`/`Py_Main` trio (gone in 3.13).
+///
+/// `C` is the version-specific [`PyConfigAbi`] mirror the caller picked from `python_version`.
+///
+/// <https://docs.python.org/3/c-api/init_config.html#initialization-with-pyconfig>
+///
+/// Returns the exit code from `Py_RunMain`.
+unsafe fn init_from_config<C: PyConfigAbi>(
+    lib: &Library,
+    python_home: &Path,
+    sys_executable: &Path,
+    args: &[String],
+) -> Result<c_int, PythonPlusPlusError> {
+    let py_config_init_python_config: libloading::Symbol<
+        unsafe extern "C" fn(*mut C) -> c_void,
+    > = lib.get(b"PyConfig_InitPythonConfig")?;
+    let py_config_set_string: libloading::Symbol<
+        unsafe extern "C" fn(*mut C, *mut *mut wchar_t, *const wchar_t) -> PyStatus,
+    > = lib.get(b"PyConfig_SetString")?;
+    let py_config_set_argv: libloading::Symbol<
+        unsafe extern "C" fn(*mut C, isize, *mut *const wchar_t) -> PyStatus,
+    > = lib.get(b"PyConfig_SetArgv")?;
+    let py_config_clear: libloading::Symbol<unsafe extern "C" fn(*mut C) -> c_void> =
+        lib.get(b"PyConfig_Clear")?;
+    let py_initialize_from_config: libloading::Symbol<
+        unsafe extern "C" fn(*const C) -> PyStatus,
+    > = lib.get(b"Py_InitializeFromConfig")?;
+    let py_status_exception: libloading::Symbol<unsafe extern "C" fn(PyStatus) -> c_int> =
+        lib.get(b"PyStatus_Exception")?;
+    let py_exit_status_exception: libloading::Symbol<unsafe extern "C" fn(PyStatus) -> !> =
+        lib.get(b"Py_ExitStatusException")?;
+
+    // Same bail-out as the pre-init helper: hand a failing PyStatus straight to cpython.
+    let check = |status: PyStatus| {
+        #[allow(unreachable_code)]
+        if py_status_exception(status) != 0 {
+            debug!("libpython config error: {:?}", status);
+            py_exit_status_exception(status);
+            #[allow(unreachable_code)]
+            {
+                unreachable!();
+            }
+        }
+    };
+
+    let mut config: MaybeUninit<C> = MaybeUninit::uninit();
+    py_config_init_python_config(config.as_mut_ptr());
+    let config = config.as_mut_ptr();
+
+    trace!("PyConfig home {}", python_home.display());
+    // PyConfig_SetString copies the string, so the WideCString can be dropped right after.
+    let python_home_wchar_t = WideCString::from_str(python_home.to_string_lossy()).unwrap();
+    check(py_config_set_string(
+        config,
+        C::home(config),
+        python_home_wchar_t.as_ptr() as *const wchar_t,
+    ));
+
+    let sys_executable_str = sys_executable
+        .to_str()
+        .ok_or_else(|| PythonPlusPlusError::InvalidPath(sys_executable.to_path_buf()))?;
+    trace!("PyConfig program_name {}", sys_executable_str);
+    let sys_executable_wchar_t = WideCString::from_str(sys_executable_str).unwrap();
+    check(py_config_set_string(
+        config,
+        C::program_name(config),
+        sys_executable_wchar_t.as_ptr() as *const wchar_t,
+    ));
+
+    // env::args panics when there is a non utf-8 string, but converting OsString -> *c_char
+    // is an even bigger mess
+    let args_cstring: Vec<WideCString> = args
+        .iter()
+        .map(|arg| WideCString::from_str(arg).unwrap())
+        .collect();
+    let mut args_wchar_t: Vec<*const wchar_t> = args_cstring
+        .iter()
+        .map(|arg| arg.as_ptr() as *const wchar_t)
+        .collect();
+    check(py_config_set_argv(
+        config,
+        args_wchar_t.len() as isize,
+        args_wchar_t.as_mut_ptr(),
+    ));
+
+    debug!("Py_InitializeFromConfig: {}", args.join(" "));
+    let status = py_initialize_from_config(config);
+    check(status);
+    py_config_clear(config);
+
+    // https://docs.python.org/3/c-api/veryhigh.html#c.Py_RunMain
+    // Runs the interpreter as configured (the equivalent of the former Py_Main) and returns the
+    // exit code; let the caller exit with that status if python didn't.
+    let py_run_main: libloading::Symbol<unsafe extern "C" fn() -> c_int> =
+        lib.get(b"Py_RunMain")?;
+    Ok(py_run_main())
+}
+
+/// Initialize and run PyPy through its stable cffi embedding entry points instead of the
+/// CPython-only `PyConfig` flow. PyPy exports none of the `PyConfig_*`/`Py_InitializeFromConfig`
+/// symbols, so [`init_from_config`] would fail at the very first `lib.get`; the supported path is
+/// `rpython_startup_code` + `pypy_setup_home` + `pypy_execute_source`.
+///
+/// <https://doc.pypy.org/en/latest/embedding.html>
+///
+/// Returns the program's exit code. The bootstrap snippet calls `os._exit` itself so `SystemExit`
+/// codes survive (see [`pypy_program_source`]); this only returns when the program raised an
+/// uncaught exception, which becomes exit code 1.
+unsafe fn init_pypy(
+    lib: &Library,
+    python_home: &Path,
+    args: &[String],
+) -> Result<c_int, PythonPlusPlusError> {
+    let rpython_startup_code: libloading::Symbol<unsafe extern "C" fn() -> c_int> =
+        lib.get(b"rpython_startup_code")?;
+    let pypy_setup_home: libloading::Symbol<
+        unsafe extern "C" fn(*const c_char, c_int) -> c_int,
+    > = lib.get(b"pypy_setup_home")?;
+    let pypy_execute_source: libloading::Symbol<unsafe extern "C" fn(*const c_char) -> c_int> =
+        lib.get(b"pypy_execute_source")?;
+
+    rpython_startup_code();
+
+    // `pypy_setup_home` wants the path to the libpypy3-c library (or the home directory); the
+    // second argument is the verbosity flag, matching the embedding example.
+    let home = CString::new(python_home.to_string_lossy().as_bytes())
+        .map_err(|_| PythonPlusPlusError::InvalidPath(python_home.to_path_buf()))?;
+    if pypy_setup_home(home.as_ptr(), 1) != 0 {
+        return Err(PythonPlusPlusError::PyPyInit(format!(
+            "pypy_setup_home failed for {}",
+            python_home.display()
+        )));
+    }
+
+    // PyPy has no `Py_RunMain`, so we drive execution by feeding it a bootstrap snippet that
+    // replays `sys.argv` and runs the program the same way `Py_RunMain` would from the rewritten
+    // args, surfacing the `SystemExit` code by calling `os._exit` with it.
+    let source = CString::new(pypy_program_source(args))
+        .map_err(|err| PythonPlusPlusError::PyPyInit(err.to_string()))?;
+    debug!("pypy_execute_source: {}", args.join(" "));
+    // The snippet ends in `os._exit(_code)`, so on a clean run the process exits there and this
+    // call never returns. We only get here when the program raised an uncaught exception, which
+    // `pypy_execute_source` reports as `-1`; map that to the conventional failure code.
+    match pypy_execute_source(source.as_ptr()) {
+        0 => Ok(0),
+        _ => Ok(1),
+    }
+}
+
+/// Turn the rewritten CPython-style args into a snippet PyPy can `pypy_execute_source`: set
+/// `sys.argv` and dispatch on `-c <code>` vs a script path, mirroring what `Py_RunMain` does for
+/// the same args. `pypy_execute_source` only reports `0`/`-1` and ignores the trailing expression
+/// value, so we surface the real exit status by calling `os._exit` ourselves (after flushing the
+/// std streams, which `os._exit` would otherwise skip).
+fn pypy_program_source(args: &[String]) -> String {
+    // args[0] is the interpreter binary; everything after is what Py_RunMain would see.
+    let rest = &args[1..];
+    let (argv, body): (Vec<String>, String) = if rest.first().map(String::as_str) == Some("-c") {
+        // `python -c <code> a b` -> sys.argv == ['-c', 'a', 'b'], code runs as __main__.
+        let code = rest.get(1).cloned().unwrap_or_default();
+        let mut argv = vec!["-c".to_string()];
+        argv.extend(rest.iter().skip(2).cloned());
+        (
+            argv,
+            format!(
+                "exec(compile({code}, '<string>', 'exec'), {{'__name__': '__main__'}})",
+                code = py_str_literal(&code)
+            ),
+        )
+    } else {
+        // `python script.py a b` -> sys.argv == ['script.py', 'a', 'b'], script runs as __main__.
+        (
+            rest.to_vec(),
+            "runpy.run_path(sys.argv[0], run_name='__main__')".to_string(),
+        )
+    };
+    format!(
+        "import sys, runpy, os\n\
+         sys.argv = {argv}\n\
+         _code = 0\n\
+         try:\n\
+         \x20   {body}\n\
+         except SystemExit as exc:\n\
+         \x20   _code = exc.code if isinstance(exc.code, int) else (0 if exc.code is None else 1)\n\
+         sys.stdout.flush()\n\
+         sys.stderr.flush()\n\
+         os._exit(_code)\n",
+        argv = py_list_literal(&argv),
+        body = body,
+    )
+}
+
+/// Emit `s` as a single-quoted Python string literal with the handful of escapes we can actually
+/// hit in a path or `-c` payload.
+fn py_str_literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// Emit `items` as a Python list literal, reusing [`py_str_literal`] per element.
+fn py_list_literal(items: &[String]) -> String {
+    let inner: Vec<String> = items.iter().map(|item| py_str_literal(item)).collect();
+    format!("[{}]", inner.join(", "))
+}
+
+/// The subset of `sysconfig` config vars that pyo3's build scripts model as `InterpreterConfig`,
+/// queried from the live interpreter so we load the shared library under its real name instead of
+/// guessing. Guessing breaks on debug builds (the `d` suffix), distro multiarch layouts, and
+/// version-suffixed sonames like `libpython3.10.so.1.0`.
+// `ld_version`/`base_prefix` round out the pyo3 `InterpreterConfig` shape; we keep them for
+// logging and future callers even though the load path only needs `libdir`/`inst_so_name`/`shared`.
+#[allow(dead_code)]
+#[derive(Debug)]
+struct InterpreterConfig {
+    /// `LIBDIR`, the directory the shared library lives in.
+    libdir: Option<String>,
+    /// `INSTSONAME` (falling back to `LDLIBRARY`): the actual shared library file name.
+    inst_so_name: Option<String>,
+    /// `LDVERSION`, e.g. `3.10` or `3.13d` for a debug build.
+    ld_version: Option<String>,
+    /// `Py_ENABLE_SHARED`: whether libpython is a shared library at all.
+    shared: bool,
+    /// `sys.base_prefix`, the interpreter's installation root.
+    base_prefix: Option<String>,
+}
+
+/// Ask the provisioned interpreter where its shared library actually is, mirroring how the pyo3
+/// build scripts populate `InterpreterConfig`. Returns `None` (and the caller falls back to the
+/// name heuristic) if the interpreter can't be run or exits unsuccessfully; vars missing from its
+/// output are left as `None` in the returned config.
+fn query_interpreter_config(python_binary: &Path) -> Option<InterpreterConfig> {
+    let script = "import sysconfig, sys\n\
+        for k, v in [\n\
+        ('LIBDIR', sysconfig.get_config_var('LIBDIR')),\n\
+        ('INSTSONAME', sysconfig.get_config_var('INSTSONAME')),\n\
+        ('LDLIBRARY', sysconfig.get_config_var('LDLIBRARY')),\n\
+        ('LDVERSION', sysconfig.get_config_var('LDVERSION')),\n\
+        ('Py_ENABLE_SHARED', sysconfig.get_config_var('Py_ENABLE_SHARED')),\n\
+        ('base_prefix', sys.base_prefix),\n\
+        ]:\n\
+        \x20   print('{}={}'.format(k, v))\n";
+    let output = Command::new(python_binary).arg("-c").arg(script).output();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        other => {
+            debug!("Couldn't query sysconfig from {python_binary:?}: {other:?}");
+            return None;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // `sysconfig.get_config_var` returns `None` when a var is undefined, which prints as the literal
+    // `None`; treat that as absent.
+    let mut vars = std::collections::HashMap::new();
+    for line in stdout.lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            if value != "None" {
+                vars.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    trace!("sysconfig from {python_binary:?}: {vars:?}");
+
+    Some(InterpreterConfig {
+        libdir: vars.get("LIBDIR").cloned(),
+        inst_so_name: vars
+            .get("INSTSONAME")
+            .or_else(|| vars.get("LDLIBRARY"))
+            .cloned(),
+        ld_version: vars.get("LDVERSION").cloned(),
+        shared: vars.get("Py_ENABLE_SHARED").map(|v| v == "1").unwrap_or(false),
+        base_prefix: vars.get("base_prefix").cloned(),
+    })
+}
+
+/// Ask the interpreter which implementation it is so we pick the right shared library and init
+/// sequence. Defaults to CPython (the only kind the monotrail provisioner fetches) when the query
+/// fails, and errors out for implementations we don't embed rather than crashing later in `lib.get`.
+fn determine_interpreter_kind(
+    python_binary: &Path,
+) -> Result<InterpreterKind, PythonPlusPlusError> {
+    let output = Command::new(python_binary)
+        .arg("-c")
+        .arg("import sys; print(sys.implementation.name)")
+        .output();
+    let name = match output {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        other => {
+            debug!("Couldn't determine interpreter kind from {python_binary:?}, assuming CPython: {other:?}");
+            return Ok(InterpreterKind::CPython);
+        }
+    };
+    match name.as_str() {
+        "cpython" => Ok(InterpreterKind::CPython),
+        "pypy" => Ok(InterpreterKind::PyPy),
+        other => Err(PythonPlusPlusError::UnsupportedInterpreter(other.to_string())),
+    }
+}
+
+/// Locate the shared library to load. `PYPP_LIBPYTHON` wins when set; otherwise prefer what the
+/// interpreter reports via [`query_interpreter_config`], and fall back to the per-OS, per-kind name
+/// heuristic when the query fails, the interpreter is statically linked, `LIBDIR`/`INSTSONAME` are
+/// missing, or the reported file doesn't exist.
+fn resolve_libpython(
+    python_home: &Path,
+    python_version: (u8, u8),
+    python_binary: &Path,
+    kind: InterpreterKind,
+) -> PathBuf {
+    // The external-config escape hatch wins over any discovery: point it straight at the library.
+    if let Some(libpython) = env::var_os("PYPP_LIBPYTHON") {
+        let libpython = PathBuf::from(libpython);
+        trace!("libpython from PYPP_LIBPYTHON: {}", libpython.display());
+        return libpython;
+    }
+
+    if let Some(config) = query_interpreter_config(python_binary) {
+        if config.shared {
+            if let (Some(libdir), Some(inst_so_name)) = (&config.libdir, &config.inst_so_name) {
+                let libpython3 = Path::new(libdir).join(inst_so_name);
+                // `LIBDIR` is the build-time prefix, which for the python-build-standalone builds
+                // the monotrail provisioner fetches routinely points at a path that doesn't exist on
+                // this machine. Only trust it when the file is actually there; otherwise degrade to
+                // the name heuristic instead of handing `lib.get` a bogus path.
+                if libpython3.is_file() {
+                    trace!("libpython from sysconfig: {}", libpython3.display());
+                    return libpython3;
+                }
+                debug!(
+                    "sysconfig libpython {} doesn't exist, falling back to the name heuristic",
+                    libpython3.display()
+                );
+            }
+        }
+        debug!("sysconfig didn't yield a shared libpython, falling back to the name heuristic");
+    }
+
+    match kind {
+        InterpreterKind::CPython => {
+            if cfg!(target_os = "windows") {
+                // python3.dll doesn't include functions from the limited abi apparently
+                python_home.join(format!("python3{}.dll", python_version.1))
+            } else if cfg!(target_os = "macos") {
+                python_home.join("lib").join(format!(
+                    "libpython{}.{}.dylib",
+                    python_version.0, python_version.1
+                ))
+            } else {
+                python_home.join("lib").join("libpython3.so")
+            }
+        }
+        InterpreterKind::PyPy => {
+            if cfg!(target_os = "windows") {
+                python_home.join("pypy3-c.dll")
+            } else if cfg!(target_os = "macos") {
+                python_home.join("lib").join("libpypy3-c.dylib")
+            } else {
+                python_home.join("lib").join("libpypy3-c.so")
+            }
+        }
+    }
+}
+
 /// The way we're using to load symbol by symbol with the type generic is really ugly and cumbersome
 /// If you know how to do this with `extern` or even pyo3-ffi directly please tell me.
 ///
@@ -129,26 +890,18 @@ unsafe fn pre_init(lib: &Library) -> Result<(), PythonPlusPlusError> {
 fn inject_and_run_python(
     python_home: &Path,
     python_version: (u8, u8),
+    kind: InterpreterKind,
     sys_executable: &Path,
     args: &[String],
 ) -> Result<c_int, PythonPlusPlusError> {
     trace!(
-        "Loading libpython {}.{}",
+        "Loading {:?} {}.{}",
+        kind,
         python_version.0,
         python_version.1
     );
 
-    let libpython3 = if cfg!(target_os = "windows") {
-        // python3.dll doesn't include functions from the limited abi apparently
-        python_home.join(format!("python3{}.dll", python_version.1))
-    } else if cfg!(target_os = "macos") {
-        python_home.join("lib").join(format!(
-            "libpython{}.{}.dylib",
-            python_version.0, python_version.1
-        ))
-    } else {
-        python_home.join("lib").join("libpython3.so")
-    };
+    let libpython3 = resolve_libpython(python_home, python_version, sys_executable, kind);
     let lib = {
         // platform switch because we need to set RTLD_GLOBAL so extension modules work later
         #[cfg(unix)]
@@ -170,66 +923,32 @@ fn inject_and_run_python(
         // TODO: Do this via python c api instead
         env::set_var("PYTHONNOUSERSITE", "1");
 
-        pre_init(&lib)?;
-
-        trace!("Py_SetPythonHome {}", python_home.display());
-        // https://docs.python.org/3/c-api/init.html#c.Py_SetPythonHome
-        // void Py_SetPythonHome(const wchar_t *name)
-        // Otherwise we get an error that it can't find encoding that tells us to set PYTHONHOME
-        let set_python_home: libloading::Symbol<unsafe extern "C" fn(*const wchar_t) -> c_void> =
-            lib.get(b"Py_SetPythonHome")?;
-        let python_home_wchar_t = WideCString::from_str(python_home.to_string_lossy()).unwrap();
-        set_python_home(python_home_wchar_t.as_ptr() as *const wchar_t);
-
-        let sys_executable_str = sys_executable
-            .to_str()
-            .ok_or_else(|| PythonPlusPlusError::InvalidPath(sys_executable.to_path_buf()))?;
         if !sys_executable.is_file() {
             return Err(PythonPlusPlusError::NoSuchExecutable(
-                sys_executable_str.to_string(),
+                sys_executable.to_string_lossy().to_string(),
             ));
         }
 
-        trace!("Py_SetProgramName {}", sys_executable_str);
-        // https://docs.python.org/3/c-api/init.html#c.Py_SetProgramName
-        // void Py_SetProgramName(const wchar_t *name)
-        // To set sys.executable
-        let set_program_name: libloading::Symbol<unsafe extern "C" fn(*const wchar_t) -> c_void> =
-            lib.get(b"Py_SetProgramName")?;
-        let sys_executable = WideCString::from_str(sys_executable_str).unwrap();
-        set_program_name(sys_executable.as_ptr() as *const wchar_t);
-
-        trace!("Py_Initialize");
-        // https://docs.python.org/3/c-api/init.html?highlight=py_initialize#c.Py_Initialize
-        // void Py_Initialize()
-        let initialize: libloading::Symbol<unsafe extern "C" fn() -> c_void> =
-            lib.get(b"Py_Initialize")?;
-        initialize();
-
-        debug!("Running Py_Main: {}", args.join(" "));
-        // run python interpreter as from the cli
-        // https://docs.python.org/3/c-api/veryhigh.html#c.Py_BytesMain
-        let py_main: libloading::Symbol<unsafe extern "C" fn(c_int, *mut *const wchar_t) -> c_int> =
-            lib.get(b"Py_Main")?;
-
-        // env::args panics when there is a non utf-8 string, but converting OsString -> *c_char
-        // is an even bigger mess
-        let args_cstring: Vec<WideCString> = args
-            .iter()
-            .map(|arg| WideCString::from_str(arg).unwrap())
-            .collect();
-        let mut args_c_char: Vec<*const wchar_t> = args_cstring
-            .iter()
-            .map(|arg| arg.as_ptr() as *const wchar_t)
-            .collect();
-        let exit_code = py_main(args_cstring.len() as c_int, args_c_char.as_mut_ptr());
-        // > The return value will be 0 if the interpreter exits normally (i.e., without an
-        // > exception), 1 if the interpreter exits due to an exception, or 2 if the parameter list
-        // > does not represent a valid Python command line.
-        // >
-        // > Note that if an otherwise unhandled SystemExit is raised, this function will not
-        // > return 1, but exit the process, as long as Py_InspectFlag is not set.
-        // Let the caller exit with that status if python didn't
+        // CPython and PyPy have entirely separate embedding APIs: PyPy exports neither the
+        // `PyPreConfig` preinit symbols nor the `PyConfig_*` init-config symbols, so it takes its
+        // own `rpython_startup_code`/`pypy_setup_home` path instead of falling through into
+        // `init_from_config` and dying on the first `lib.get`.
+        let exit_code = match kind {
+            InterpreterKind::CPython => {
+                pre_init(&lib)?;
+                // Drive initialization through the version-matched PyConfig mirror. The struct
+                // layout is an ABI contract, so the minor version has to select the right one.
+                match python_version.1 {
+                    0..=10 => {
+                        init_from_config::<PyConfig310>(&lib, python_home, sys_executable, args)?
+                    }
+                    11 => init_from_config::<PyConfig311>(&lib, python_home, sys_executable, args)?,
+                    12 => init_from_config::<PyConfig312>(&lib, python_home, sys_executable, args)?,
+                    _ => init_from_config::<PyConfig313>(&lib, python_home, sys_executable, args)?,
+                }
+            }
+            InterpreterKind::PyPy => init_pypy(&lib, python_home, args)?,
+        };
         Ok(exit_code)
     }
 }
@@ -237,54 +956,96 @@ fn inject_and_run_python(
 fn run() -> Result<i32, PythonPlusPlusError> {
     // Skip the name of the rust binary
     let args: Vec<String> = env::args().collect();
-    let cache_dir = dirs::cache_dir()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "System needs to have a cache dir"))?
-        .join(env!("CARGO_PKG_NAME"));
+
+    // `pypp freeze <script>` emits a standalone single-file executable instead of running.
+    if matches!(args.get(1).map(String::as_str), Some("freeze") | Some("build")) {
+        freeze::build_command(&args[2..])?;
+        return Ok(0);
+    }
+
+    // If this launcher was produced by `freeze`, run the embedded module from memory rather than
+    // reading a script off disk. The interpreter itself is still resolved below (provisioned or
+    // via the PYPP_PYTHON_* hatch); freezing removes the script file, not the libpython dependency.
+    let frozen_source = freeze::read_embedded()?;
+
     let default_python_version = (3, 10);
-    let (args_after, python_version) =
+    let (args_after, detected_version) =
         determine_python_version(&args[1..], None, default_python_version)
             .map_err(PythonPlusPlusError::DeterminePythonVersion)?;
-    let (python_binary, python_home) = provision_python(python_version, &cache_dir)
-        .map_err(PythonPlusPlusError::ProvisionPython)?;
 
-    let Some(script) =
-        naive_python_arg_parser(&args_after).map_err(PythonPlusPlusError::CpythonArgs)?
-    else {
-        return Err(PythonPlusPlusError::MissingScript);
+    // An externally supplied interpreter bypasses provisioning entirely.
+    let external = external_interpreter_from_env()?;
+    let python_version = external
+        .as_ref()
+        .map(|external| external.python_version)
+        .unwrap_or(detected_version);
+    let (python_binary, python_home) = match external {
+        Some(external) => (external.python_binary, external.python_home),
+        None => {
+            let cache_dir = dirs::cache_dir()
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, "System needs to have a cache dir")
+                })?
+                .join(env!("CARGO_PKG_NAME"));
+            provision_python(python_version, &cache_dir)
+                .map_err(PythonPlusPlusError::ProvisionPython)?
+        }
     };
+    let kind = determine_interpreter_kind(&python_binary)?;
 
-    let content = fs::read_to_string(&script)?;
-    let formatted = format_module_source(&content, PyFormatOptions::default())?;
-    let temp_file = NamedTempFile::new()?;
-    fs::write(temp_file.path(), formatted.as_code())?;
-    let temp_file_string_name = temp_file
-        .path()
-        .to_str()
-        .ok_or_else(|| PythonPlusPlusError::InvalidPath(temp_file.path().to_path_buf()))?;
-
-    // Don't look
-    let args_after: Vec<String> = args_after
-        .into_iter()
-        .map(|arg| {
-            if arg == script {
-                temp_file_string_name.to_string()
-            } else {
-                arg
-            }
-        })
-        .collect();
-
-    let final_args: Vec<String> = [python_binary
+    let python_binary_string = python_binary
         .to_str()
         .ok_or_else(|| PythonPlusPlusError::InvalidPath(python_binary.to_path_buf()))?
-        .to_string()]
-    .into_iter()
-    .chain(args_after)
-    .collect();
+        .to_string();
+
+    // Keep the temp file alive until after the interpreter has run in the non-frozen case.
+    let mut _temp_file: Option<NamedTempFile> = None;
+    let final_args: Vec<String> = if let Some(frozen_source) = frozen_source {
+        // The source was already formatted at build time; install the in-memory importer and run it.
+        let bootstrap = freeze::bootstrap_source(&frozen_source);
+        [python_binary_string, "-c".to_string(), bootstrap]
+            .into_iter()
+            .chain(args_after)
+            .collect()
+    } else {
+        let Some(script) =
+            naive_python_arg_parser(&args_after).map_err(PythonPlusPlusError::CpythonArgs)?
+        else {
+            return Err(PythonPlusPlusError::MissingScript);
+        };
+
+        let content = fs::read_to_string(&script)?;
+        let formatted = format_module_source(&content, PyFormatOptions::default())?;
+        let temp_file = NamedTempFile::new()?;
+        fs::write(temp_file.path(), formatted.as_code())?;
+        let temp_file_string_name = temp_file
+            .path()
+            .to_str()
+            .ok_or_else(|| PythonPlusPlusError::InvalidPath(temp_file.path().to_path_buf()))?
+            .to_string();
+        _temp_file = Some(temp_file);
+
+        // Don't look
+        let args_after: Vec<String> = args_after
+            .into_iter()
+            .map(|arg| {
+                if arg == script {
+                    temp_file_string_name.clone()
+                } else {
+                    arg
+                }
+            })
+            .collect();
+
+        [python_binary_string]
+            .into_iter()
+            .chain(args_after)
+            .collect()
+    };
 
     debug!("Running cpython with {:?}", final_args);
     let exit_code =
-        inject_and_run_python(&python_home, python_version, &python_binary, &final_args)?;
+        inject_and_run_python(&python_home, python_version, kind, &python_binary, &final_args)?;
 
     Ok(exit_code)
 }